@@ -0,0 +1,145 @@
+//! A copy-on-write layer over `MockPageTable`: shares one physical frame
+//! across mappings, read-only, until a write-protect fault forces a
+//! private copy.
+
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use super::paging::{AccessReason, Entry, MockPageTable, PageTable};
+use super::{PhysAddr, VirtAddr, PAGE_SIZE};
+
+fn frame_of(target: PhysAddr) -> usize {
+    target / PAGE_SIZE
+}
+
+pub struct CowPageTable {
+    inner: MockPageTable,
+    ref_counts: Rc<RefCell<Vec<usize>>>,
+}
+
+impl CowPageTable {
+    /// `allocate_frame` is called to obtain a fresh physical frame
+    /// whenever a write-protect fault finds its page still shared.
+    pub fn new(mut allocate_frame: impl FnMut() -> PhysAddr + 'static) -> Self {
+        let ref_counts: Rc<RefCell<Vec<usize>>> = Rc::new(RefCell::new(Vec::new()));
+        let mut inner = MockPageTable::new();
+        inner.set_handler(Box::new({
+            let ref_counts = ref_counts.clone();
+            move |pt: &mut MockPageTable, addr: VirtAddr, reason: AccessReason| {
+                if reason == AccessReason::Store {
+                    Self::handle_write_fault(pt, &ref_counts, addr, &mut allocate_frame);
+                }
+            }
+        }));
+        CowPageTable { inner, ref_counts }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut MockPageTable {
+        &mut self.inner
+    }
+
+    fn adjust_ref_count(ref_counts: &Rc<RefCell<Vec<usize>>>, frame: usize, delta: isize) {
+        let mut counts = ref_counts.borrow_mut();
+        if counts.len() <= frame {
+            counts.resize(frame + 1, 0);
+        }
+        counts[frame] = (counts[frame] as isize + delta) as usize;
+    }
+
+    /// Map a fresh, exclusively-owned frame.
+    pub fn map(&mut self, addr: VirtAddr, target: PhysAddr) {
+        self.inner.map(addr, target);
+        Self::adjust_ref_count(&self.ref_counts, frame_of(target), 1);
+    }
+
+    /// Make `other_addr` share `addr`'s frame, mapping it read-only on
+    /// both sides.
+    pub fn share(&mut self, addr: VirtAddr, other_addr: VirtAddr) {
+        let target = self.inner.get_entry(addr).target();
+        self.inner.get_entry(addr).set_writable(false);
+        self.inner.map(other_addr, target);
+        self.inner.get_entry(other_addr).set_writable(false);
+        Self::adjust_ref_count(&self.ref_counts, frame_of(target), 1);
+    }
+
+    /// Write-protect fault handler wired into `inner` by `new`: copies
+    /// the shared frame if `addr` isn't its sole owner, otherwise just
+    /// hands write access back.
+    fn handle_write_fault(
+        pt: &mut MockPageTable,
+        ref_counts: &Rc<RefCell<Vec<usize>>>,
+        addr: VirtAddr,
+        allocate_frame: &mut FnMut() -> PhysAddr,
+    ) {
+        let page_addr = addr - addr % PAGE_SIZE;
+        let old_target = pt.get_entry(page_addr).target();
+        let frame = frame_of(old_target);
+        if ref_counts.borrow()[frame] <= 1 {
+            pt.get_entry(page_addr).set_writable(true);
+            return;
+        }
+
+        let new_frame = allocate_frame();
+        let mut buf = [0u8; PAGE_SIZE];
+        for (i, byte) in buf.iter_mut().enumerate() {
+            *byte = pt.read(page_addr + i);
+        }
+        pt.unmap(page_addr);
+        pt.map(page_addr, new_frame);
+        for (i, byte) in buf.iter().enumerate() {
+            pt.write(page_addr + i, *byte);
+        }
+
+        Self::adjust_ref_count(ref_counts, frame, -1);
+        Self::adjust_ref_count(ref_counts, frame_of(new_frame), 1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bump_allocator(mut next: PhysAddr) -> impl FnMut() -> PhysAddr {
+        move || {
+            let frame = next;
+            next += PAGE_SIZE;
+            frame
+        }
+    }
+
+    #[test]
+    fn write_to_shared_frame_duplicates() {
+        let mut pt = CowPageTable::new(bump_allocator(0x3000));
+        pt.map(0x0, 0x1000);
+        pt.share(0x0, 0x2000);
+
+        // Writing through the shared, read-only mapping faults and
+        // transparently gets its own copy.
+        pt.inner_mut().write(0x2000, 1);
+        assert!(pt.inner_mut().get_entry(0x2000).writable());
+        assert_eq!(pt.inner_mut().get_entry(0x2000).target(), 0x3000);
+
+        // The original frame is now solely owned by 0x0, so its own
+        // write-protect fault just reclaims write access in place.
+        pt.inner_mut().write(0x0, 2);
+        assert!(pt.inner_mut().get_entry(0x0).writable());
+        assert_eq!(pt.inner_mut().get_entry(0x0).target(), 0x1000);
+    }
+
+    #[test]
+    fn copied_page_keeps_its_contents() {
+        let mut pt = CowPageTable::new(bump_allocator(0x3000));
+        pt.map(0x0, 0x1000);
+        pt.inner_mut().write(0x1, 42);
+        pt.share(0x0, 0x2000);
+
+        pt.inner_mut().write(0x2000, 99);
+        // The original mapping at 0x0 still sees the unmodified frame.
+        assert_eq!(pt.inner_mut().read(0x1), 42);
+        // The fresh copy behind the new mapping kept the old byte...
+        assert_eq!(pt.inner_mut().read(0x2001), 42);
+        // ...and took the write that triggered the copy.
+        assert_eq!(pt.inner_mut().read(0x2000), 99);
+    }
+}
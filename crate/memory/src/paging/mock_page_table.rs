@@ -15,6 +15,9 @@ pub struct MockEntry {
     target: PhysAddr,
     present: bool,
     writable: bool,
+    readable: bool,
+    executable: bool,
+    user: bool,
     accessed: bool,
     dirty: bool,
 }
@@ -24,14 +27,20 @@ impl Entry for MockEntry {
     fn dirty(&self) -> bool { self.dirty }
     fn writable(&self) -> bool { self.writable }
     fn present(&self) -> bool { self.present }
+    fn readable(&self) -> bool { self.readable }
+    fn executable(&self) -> bool { self.executable }
+    fn user(&self) -> bool { self.user }
     fn clear_accessed(&mut self) { self.accessed = false; }
     fn clear_dirty(&mut self) { self.dirty = false; }
     fn set_writable(&mut self, value: bool) { self.writable = value; }
     fn set_present(&mut self, value: bool) { self.present = value; }
+    fn set_readable(&mut self, value: bool) { self.readable = value; }
+    fn set_executable(&mut self, value: bool) { self.executable = value; }
+    fn set_user(&mut self, value: bool) { self.user = value; }
     fn target(&self) -> usize { self.target }
 }
 
-type PageFaultHandler = Box<FnMut(&mut MockPageTable, VirtAddr)>;
+type PageFaultHandler = Box<FnMut(&mut MockPageTable, VirtAddr, AccessReason)>;
 
 impl PageTable for MockPageTable {
     type Entry = MockEntry;
@@ -42,6 +51,7 @@ impl PageTable for MockPageTable {
         assert!(!entry.present);
         entry.present = true;
         entry.writable = true;
+        entry.readable = true;
         entry.target = target & !(PAGE_SIZE - 1);
         entry
     }
@@ -68,11 +78,11 @@ impl MockPageTable {
     pub fn set_handler(&mut self, page_fault_handler: PageFaultHandler) {
         self.page_fault_handler = Some(page_fault_handler);
     }
-    fn trigger_page_fault(&mut self, addr: VirtAddr) {
+    fn trigger_page_fault(&mut self, addr: VirtAddr, reason: AccessReason) {
         // In order to call the handler with &mut self as an argument
         // We have to first take the handler out of self, finally put it back
         let mut handler = self.page_fault_handler.take().unwrap();
-        handler(self, addr);
+        handler(self, addr, reason);
         self.page_fault_handler = Some(handler);
     }
     fn translate(&self, addr: VirtAddr) -> PhysAddr {
@@ -85,29 +95,89 @@ impl MockPageTable {
         assert!(pa < self.data.len(), "Physical memory access out of range");
         &mut self.data[pa]
     }
-    /// Read memory, mark accessed, trigger page fault if not present
+    /// Read memory, mark accessed, trigger page fault if not present or
+    /// not readable
     pub fn read(&mut self, addr: VirtAddr) -> u8 {
-        while !self.entries[addr / PAGE_SIZE].present {
-            self.trigger_page_fault(addr);
+        while !(self.entries[addr / PAGE_SIZE].present && self.entries[addr / PAGE_SIZE].readable) {
+            self.trigger_page_fault(addr, AccessReason::Load);
         }
         self.entries[addr / PAGE_SIZE].accessed = true;
         *self.get_data_mut(addr)
     }
-    /// Write memory, mark accessed and dirty, trigger page fault if not present
+    /// Write memory, mark accessed and dirty, trigger page fault if not
+    /// present *or* if present but read-only (a write-protect fault, the
+    /// mechanism copy-on-write relies on to intercept a write before it
+    /// happens).
     pub fn write(&mut self, addr: VirtAddr, data: u8) {
         while !(self.entries[addr / PAGE_SIZE].present && self.entries[addr / PAGE_SIZE].writable) {
-            self.trigger_page_fault(addr);
+            self.trigger_page_fault(addr, AccessReason::Store);
         }
         self.entries[addr / PAGE_SIZE].accessed = true;
         self.entries[addr / PAGE_SIZE].dirty = true;
         *self.get_data_mut(addr) = data;
     }
+    /// Fetch an instruction byte, mark accessed, trigger page fault if
+    /// not present or not executable (W^X enforcement)
+    pub fn execute(&mut self, addr: VirtAddr) -> u8 {
+        while !(self.entries[addr / PAGE_SIZE].present && self.entries[addr / PAGE_SIZE].executable) {
+            self.trigger_page_fault(addr, AccessReason::Execute);
+        }
+        self.entries[addr / PAGE_SIZE].accessed = true;
+        *self.get_data_mut(addr)
+    }
+    /// Read `buf.len()` bytes starting at `addr`, like a software MMU's
+    /// counted `memory_access`. Copies one page segment at a time:
+    /// presence/readability is only checked (and faulted on) once per
+    /// page crossed, not once per byte, so a fault handler that maps the
+    /// next page partway through lets the read carry on from where it
+    /// stopped.
+    pub fn read_bytes(&mut self, addr: VirtAddr, buf: &mut [u8]) {
+        let mut pos = 0;
+        while pos < buf.len() {
+            let cur = addr + pos;
+            let page = cur / PAGE_SIZE;
+            while !(self.entries[page].present && self.entries[page].readable) {
+                self.trigger_page_fault(cur, AccessReason::Load);
+            }
+            self.entries[page].accessed = true;
+
+            let page_offset = cur % PAGE_SIZE;
+            let len = core::cmp::min(buf.len() - pos, PAGE_SIZE - page_offset);
+            let pa = self.translate(cur);
+            assert!(pa + len <= self.data.len(), "Physical memory access out of range");
+            buf[pos..pos + len].copy_from_slice(&self.data[pa..pa + len]);
+            pos += len;
+        }
+    }
+    /// Write `data` starting at `addr`, copying one page segment at a
+    /// time and re-checking presence/writability (and faulting) only at
+    /// page crossings, just like `read_bytes`.
+    pub fn write_bytes(&mut self, addr: VirtAddr, data: &[u8]) {
+        let mut pos = 0;
+        while pos < data.len() {
+            let cur = addr + pos;
+            let page = cur / PAGE_SIZE;
+            while !(self.entries[page].present && self.entries[page].writable) {
+                self.trigger_page_fault(cur, AccessReason::Store);
+            }
+            self.entries[page].accessed = true;
+            self.entries[page].dirty = true;
+
+            let page_offset = cur % PAGE_SIZE;
+            let len = core::cmp::min(data.len() - pos, PAGE_SIZE - page_offset);
+            let pa = self.translate(cur);
+            assert!(pa + len <= self.data.len(), "Physical memory access out of range");
+            self.data[pa..pa + len].copy_from_slice(&data[pos..pos + len]);
+            pos += len;
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use alloc::arc::Arc;
+    use alloc::vec::Vec;
     use core::cell::RefCell;
 
     #[test]
@@ -134,6 +204,9 @@ mod test {
             let entry = pt.get_entry(0);
             assert!(entry.present());
             assert!(entry.writable());
+            assert!(entry.readable());
+            assert!(!entry.executable());
+            assert!(!entry.user());
             assert!(!entry.accessed());
             assert!(!entry.dirty());
             assert_eq!(entry.target(), 0x1000);
@@ -158,6 +231,13 @@ mod test {
 
         pt.get_entry(0).set_present(false);
         assert!(!pt.get_entry(0).present());
+
+        pt.get_entry(0).set_present(true);
+        pt.get_entry(0).set_executable(true);
+        assert!(pt.get_entry(0).executable());
+
+        pt.get_entry(0).set_user(true);
+        assert!(pt.get_entry(0).user());
     }
 
     #[test]
@@ -167,7 +247,7 @@ mod test {
         let mut pt = MockPageTable::new();
         pt.set_handler(Box::new({
             let page_fault_count1 = page_fault_count.clone();
-            move |pt: &mut MockPageTable, addr: VirtAddr| {
+            move |pt: &mut MockPageTable, addr: VirtAddr, _reason: AccessReason| {
                 *page_fault_count1.borrow_mut() += 1;
                 pt.map(addr, addr);
             }
@@ -184,4 +264,105 @@ mod test {
         pt.read(0);
         assert_eq!(*page_fault_count.borrow(), 2);
     }
+
+    #[test]
+    fn write_protect_fault_reason() {
+        let reasons = Arc::new(RefCell::new(Vec::new()));
+
+        let mut pt = MockPageTable::new();
+        pt.set_handler(Box::new({
+            let reasons = reasons.clone();
+            move |pt: &mut MockPageTable, addr: VirtAddr, reason: AccessReason| {
+                reasons.borrow_mut().push(reason);
+                pt.get_entry(addr).set_writable(true);
+            }
+        }));
+
+        pt.map(0, 0);
+        pt.get_entry(0).set_writable(false);
+
+        // Present but read-only: a Store must still fault (write-protect).
+        pt.write(0, 1);
+        assert_eq!(*reasons.borrow(), vec![AccessReason::Store]);
+    }
+
+    #[test]
+    fn non_readable_page_faults_on_load() {
+        let reasons = Arc::new(RefCell::new(Vec::new()));
+
+        let mut pt = MockPageTable::new();
+        pt.set_handler(Box::new({
+            let reasons = reasons.clone();
+            move |pt: &mut MockPageTable, addr: VirtAddr, reason: AccessReason| {
+                reasons.borrow_mut().push(reason);
+                pt.get_entry(addr).set_readable(true);
+            }
+        }));
+
+        pt.map(0, 0);
+        pt.get_entry(0).set_readable(false);
+
+        pt.read(0);
+        assert_eq!(*reasons.borrow(), vec![AccessReason::Load]);
+    }
+
+    #[test]
+    fn non_executable_page_faults_on_fetch() {
+        let reasons = Arc::new(RefCell::new(Vec::new()));
+
+        let mut pt = MockPageTable::new();
+        pt.set_handler(Box::new({
+            let reasons = reasons.clone();
+            move |pt: &mut MockPageTable, addr: VirtAddr, reason: AccessReason| {
+                reasons.borrow_mut().push(reason);
+                pt.get_entry(addr).set_executable(true);
+            }
+        }));
+
+        // Freshly mapped pages are not executable (W^X).
+        pt.map(0, 0);
+        pt.execute(0);
+        assert_eq!(*reasons.borrow(), vec![AccessReason::Execute]);
+    }
+
+    #[test]
+    fn read_write_bytes_across_page_boundary() {
+        let mut pt = MockPageTable::new();
+        pt.map(0x0, 0x0);
+        pt.map(0x1000, 0x1000);
+
+        let data: Vec<u8> = (0..8).collect();
+        pt.write_bytes(0x1000 - 4, &data);
+
+        let mut buf = [0u8; 8];
+        pt.read_bytes(0x1000 - 4, &mut buf);
+        assert_eq!(&buf[..], &data[..]);
+    }
+
+    #[test]
+    fn bulk_access_faults_mid_transfer_and_resumes() {
+        let reasons = Arc::new(RefCell::new(Vec::new()));
+
+        let mut pt = MockPageTable::new();
+        pt.set_handler(Box::new({
+            let reasons = reasons.clone();
+            move |pt: &mut MockPageTable, addr: VirtAddr, reason: AccessReason| {
+                reasons.borrow_mut().push(addr);
+                pt.map(addr, addr);
+                let _ = reason;
+            }
+        }));
+
+        // Only the first page is mapped; the write straddles into the
+        // second, which should fault exactly once, map itself, and let
+        // the transfer resume and finish.
+        pt.map(0x0, 0x0);
+        let data = [1u8, 2, 3, 4];
+        pt.write_bytes(0x1000 - 2, &data);
+
+        assert_eq!(*reasons.borrow(), vec![0x1000]);
+        let mut buf = [0u8; 4];
+        pt.read_bytes(0x1000 - 2, &mut buf);
+        assert_eq!(buf, data);
+    }
 }
\ No newline at end of file
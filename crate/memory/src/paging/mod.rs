@@ -0,0 +1,49 @@
+use super::{PhysAddr, VirtAddr};
+
+pub mod mock_page_table;
+pub mod multi_level;
+
+pub use self::mock_page_table::MockPageTable;
+pub use self::multi_level::MultiLevelPageTable;
+
+/// A single page table entry, exposing the status and permission bits a
+/// software MMU needs to track for one mapped page.
+pub trait Entry {
+    fn accessed(&self) -> bool;
+    fn dirty(&self) -> bool;
+    fn writable(&self) -> bool;
+    fn present(&self) -> bool;
+    fn readable(&self) -> bool;
+    fn executable(&self) -> bool;
+    fn user(&self) -> bool;
+    fn clear_accessed(&mut self);
+    fn clear_dirty(&mut self);
+    fn set_writable(&mut self, value: bool);
+    fn set_present(&mut self, value: bool);
+    fn set_readable(&mut self, value: bool);
+    fn set_executable(&mut self, value: bool);
+    fn set_user(&mut self, value: bool);
+    fn target(&self) -> PhysAddr;
+}
+
+/// Why a page table access faulted, so a handler can tell a read fault
+/// from a write fault (needed for e.g. copy-on-write, which must only
+/// duplicate a page on a *write* to a read-only present page) from an
+/// instruction fetch.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum AccessReason {
+    Load,
+    Store,
+    Execute,
+}
+
+/// A page table mapping virtual addresses to physical frames, one page
+/// (`PAGE_SIZE` bytes) at a time.
+pub trait PageTable {
+    type Entry: Entry;
+
+    /// Map a page, returning its entry
+    fn map(&mut self, addr: VirtAddr, target: PhysAddr) -> &mut Self::Entry;
+    fn unmap(&mut self, addr: VirtAddr);
+    fn get_entry(&mut self, addr: VirtAddr) -> &mut Self::Entry;
+}
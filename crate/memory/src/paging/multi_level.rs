@@ -0,0 +1,307 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use super::*;
+
+const PAGE_SIZE: usize = 4096;
+const PAGE_SIZE_BITS: usize = 12;
+const BITS_PER_LEVEL: usize = 9;
+const ENTRIES_PER_TABLE: usize = 1 << BITS_PER_LEVEL;
+
+/// Number of levels matching RISC-V Sv39 (3 levels x 9 bits), for passing
+/// to `MultiLevelPageTable::new`.
+pub const SV39_LEVELS: usize = 3;
+
+#[derive(Default, Copy, Clone)]
+pub struct MultiLevelEntry {
+    target: PhysAddr,
+    present: bool,
+    writable: bool,
+    readable: bool,
+    executable: bool,
+    user: bool,
+    accessed: bool,
+    dirty: bool,
+}
+
+impl Entry for MultiLevelEntry {
+    fn accessed(&self) -> bool { self.accessed }
+    fn dirty(&self) -> bool { self.dirty }
+    fn writable(&self) -> bool { self.writable }
+    fn present(&self) -> bool { self.present }
+    fn readable(&self) -> bool { self.readable }
+    fn executable(&self) -> bool { self.executable }
+    fn user(&self) -> bool { self.user }
+    fn clear_accessed(&mut self) { self.accessed = false; }
+    fn clear_dirty(&mut self) { self.dirty = false; }
+    fn set_writable(&mut self, value: bool) { self.writable = value; }
+    fn set_present(&mut self, value: bool) { self.present = value; }
+    fn set_readable(&mut self, value: bool) { self.readable = value; }
+    fn set_executable(&mut self, value: bool) { self.executable = value; }
+    fn set_user(&mut self, value: bool) { self.user = value; }
+    fn target(&self) -> PhysAddr { self.target }
+}
+
+/// One node of the radix tree: an intermediate table of child nodes, or
+/// (at the last level) a leaf entry.
+enum Node {
+    Table(Vec<Option<Box<Node>>>),
+    Leaf(MultiLevelEntry),
+}
+
+impl Node {
+    fn new_table() -> Self {
+        let mut children = Vec::with_capacity(ENTRIES_PER_TABLE);
+        for _ in 0..ENTRIES_PER_TABLE {
+            children.push(None);
+        }
+        Node::Table(children)
+    }
+}
+
+type PageFaultHandler = Box<FnMut(&mut MultiLevelPageTable, VirtAddr, AccessReason)>;
+
+/// A radix-tree page table with a configurable number of levels, each
+/// indexed by `BITS_PER_LEVEL` bits of the virtual address (3 levels of
+/// 9 bits with `SV39_LEVELS`, matching RISC-V Sv39), unlike `MockPageTable`'s
+/// flat array.
+pub struct MultiLevelPageTable {
+    levels: usize,
+    root: Node,
+    page_fault_handler: Option<PageFaultHandler>,
+}
+
+impl MultiLevelPageTable {
+    pub fn new(levels: usize) -> Self {
+        assert!(levels > 0);
+        MultiLevelPageTable {
+            levels,
+            root: Node::new_table(),
+            page_fault_handler: None,
+        }
+    }
+
+    pub fn set_handler(&mut self, page_fault_handler: PageFaultHandler) {
+        self.page_fault_handler = Some(page_fault_handler);
+    }
+
+    fn trigger_page_fault(&mut self, addr: VirtAddr, reason: AccessReason) {
+        // In order to call the handler with &mut self as an argument
+        // We have to first take the handler out of self, finally put it back
+        let mut handler = self.page_fault_handler.take().unwrap();
+        handler(self, addr, reason);
+        self.page_fault_handler = Some(handler);
+    }
+
+    /// Split `addr` into `levels` VPN indices, from the root table down to
+    /// the leaf.
+    fn indices(&self, addr: VirtAddr) -> Vec<usize> {
+        (0..self.levels)
+            .map(|level| {
+                let shift = PAGE_SIZE_BITS + (self.levels - 1 - level) * BITS_PER_LEVEL;
+                (addr >> shift) & (ENTRIES_PER_TABLE - 1)
+            })
+            .collect()
+    }
+
+    /// Walk down to the leaf slot for `addr`, allocating intermediate
+    /// tables (and an absent leaf) lazily along the way.
+    fn walk_mut(&mut self, addr: VirtAddr) -> &mut MultiLevelEntry {
+        let indices = self.indices(addr);
+        let mut node = &mut self.root;
+        for &i in &indices[..indices.len() - 1] {
+            node = match node {
+                Node::Table(children) => {
+                    children[i].get_or_insert_with(|| Box::new(Node::new_table()))
+                }
+                Node::Leaf(_) => unreachable!("leaf encountered above the last level"),
+            };
+        }
+        let last = *indices.last().unwrap();
+        match node {
+            Node::Table(children) => {
+                let child = children[last]
+                    .get_or_insert_with(|| Box::new(Node::Leaf(MultiLevelEntry::default())));
+                match child.as_mut() {
+                    Node::Leaf(entry) => entry,
+                    Node::Table(_) => unreachable!("table encountered at the last level"),
+                }
+            }
+            Node::Leaf(_) => unreachable!("leaf encountered above the last level"),
+        }
+    }
+
+    /// Drop the leaf for `addr`, then free any intermediate table left
+    /// with no children along the path back to the root.
+    fn unmap_recursive(node: &mut Node, indices: &[usize]) -> bool {
+        match node {
+            Node::Table(children) => {
+                let i = indices[0];
+                if indices.len() == 1 {
+                    assert!(children[i].is_some(), "unmap of unmapped page");
+                    children[i] = None;
+                } else {
+                    let freed = {
+                        let child = children[i].as_mut().expect("unmap of unmapped page");
+                        Self::unmap_recursive(child, &indices[1..])
+                    };
+                    if freed {
+                        children[i] = None;
+                    }
+                }
+                children.iter().all(Option::is_none)
+            }
+            Node::Leaf(_) => unreachable!("leaf encountered above the last level"),
+        }
+    }
+
+    /// Translate `addr`, faulting into the configured handler (with the
+    /// given access reason) until the leaf page is present *and* permits
+    /// `reason` (e.g. present-but-read-only still faults on a `Store`,
+    /// the write-protect fault `cow` relies on), mirroring
+    /// `MockPageTable`'s `read`/`write`/`execute`.
+    pub fn translate(&mut self, addr: VirtAddr, reason: AccessReason) -> PhysAddr {
+        while !Self::permits(self.walk_mut(addr), reason) {
+            self.trigger_page_fault(addr, reason);
+        }
+        let entry = self.walk_mut(addr);
+        (entry.target & !(PAGE_SIZE - 1)) | (addr & (PAGE_SIZE - 1))
+    }
+
+    fn permits(entry: &MultiLevelEntry, reason: AccessReason) -> bool {
+        entry.present
+            && match reason {
+                AccessReason::Load => entry.readable,
+                AccessReason::Store => entry.writable,
+                AccessReason::Execute => entry.executable,
+            }
+    }
+}
+
+impl PageTable for MultiLevelPageTable {
+    type Entry = MultiLevelEntry;
+
+    /// Map a page, allocating intermediate tables as needed, and return
+    /// its entry; panics if the page is already present
+    fn map(&mut self, addr: VirtAddr, target: PhysAddr) -> &mut Self::Entry {
+        let entry = self.walk_mut(addr);
+        assert!(!entry.present);
+        entry.present = true;
+        entry.writable = true;
+        entry.readable = true;
+        entry.target = target & !(PAGE_SIZE - 1);
+        entry
+    }
+
+    fn unmap(&mut self, addr: VirtAddr) {
+        let indices = self.indices(addr);
+        Self::unmap_recursive(&mut self.root, &indices);
+    }
+
+    fn get_entry(&mut self, addr: VirtAddr) -> &mut Self::Entry {
+        self.walk_mut(addr)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::arc::Arc;
+    use core::cell::RefCell;
+
+    #[test]
+    fn index_decomposition() {
+        let pt = MultiLevelPageTable::new(SV39_LEVELS);
+        // Each level carries 9 bits, above the 12-bit page offset.
+        let addr = (0b1 << (12 + 18)) | (0b10 << (12 + 9)) | (0b11 << 12);
+        assert_eq!(pt.indices(addr), vec![0b1, 0b10, 0b11]);
+    }
+
+    #[test]
+    fn sparse_allocation() {
+        let mut pt = MultiLevelPageTable::new(SV39_LEVELS);
+        // `SV39_LEVELS` worth of indices only cover 3*9 + 12 = 39 address
+        // bits, so a candidate address for "distinct top-level index"
+        // must set a bit below that, or `indices()` masks it away and it
+        // collides right back onto the same leaf as 0x0 (bit 30 here is
+        // well inside the 27-bit VPN field, so it lands in the top-level
+        // index).
+        assert_ne!(pt.indices(0x0), pt.indices(0x4000_0000));
+
+        pt.map(0x0, 0x1000);
+        pt.map(0x4000_0000, 0x2000); // distinct top-level index (bit 30 set)
+
+        let e0 = pt.get_entry(0x0);
+        assert!(e0.present());
+        assert_eq!(e0.target(), 0x1000);
+
+        let e1 = pt.get_entry(0x4000_0000);
+        assert!(e1.present());
+        assert_eq!(e1.target(), 0x2000);
+    }
+
+    #[test]
+    fn unmap_frees_empty_intermediate_tables() {
+        let mut pt = MultiLevelPageTable::new(SV39_LEVELS);
+        pt.map(0x0, 0x1000);
+        pt.unmap(0x0);
+        match &pt.root {
+            Node::Table(children) => assert!(children.iter().all(Option::is_none)),
+            Node::Leaf(_) => panic!("root should be a table"),
+        }
+    }
+
+    #[test]
+    fn unmap_keeps_sibling_tables() {
+        let mut pt = MultiLevelPageTable::new(SV39_LEVELS);
+        pt.map(0x0, 0x1000);
+        pt.map(0x1000, 0x2000);
+        pt.unmap(0x0);
+
+        let e1 = pt.get_entry(0x1000);
+        assert!(e1.present());
+        assert_eq!(e1.target(), 0x2000);
+    }
+
+    #[test]
+    fn page_fault_on_absent_leaf() {
+        let page_fault_count = Arc::new(RefCell::new(0usize));
+
+        let mut pt = MultiLevelPageTable::new(SV39_LEVELS);
+        pt.set_handler(Box::new({
+            let page_fault_count = page_fault_count.clone();
+            move |pt: &mut MultiLevelPageTable, addr: VirtAddr, _reason: AccessReason| {
+                *page_fault_count.borrow_mut() += 1;
+                pt.map(addr, addr);
+            }
+        }));
+
+        assert_eq!(pt.translate(0x1000, AccessReason::Load), 0x1000);
+        assert_eq!(*page_fault_count.borrow(), 1);
+
+        // Already present: no further faults.
+        assert_eq!(pt.translate(0x1000, AccessReason::Load), 0x1000);
+        assert_eq!(*page_fault_count.borrow(), 1);
+    }
+
+    #[test]
+    fn write_protect_fault_reason() {
+        let reasons = Arc::new(RefCell::new(Vec::new()));
+
+        let mut pt = MultiLevelPageTable::new(SV39_LEVELS);
+        pt.set_handler(Box::new({
+            let reasons = reasons.clone();
+            move |pt: &mut MultiLevelPageTable, addr: VirtAddr, reason: AccessReason| {
+                reasons.borrow_mut().push(reason);
+                pt.get_entry(addr).set_writable(true);
+            }
+        }));
+
+        pt.map(0, 0);
+        pt.get_entry(0).set_writable(false);
+
+        // Present but read-only: a Store must still fault (write-protect),
+        // the mechanism `cow` relies on.
+        assert_eq!(pt.translate(0, AccessReason::Store), 0);
+        assert_eq!(*reasons.borrow(), vec![AccessReason::Store]);
+    }
+}